@@ -1,9 +1,9 @@
 use crate::sys::journal as journal_c;
 use chrono::NaiveDateTime;
-use libc::{c_void, size_t};
+use libc::{c_int, c_void, iovec, size_t};
 use std::collections::HashMap;
 use std::convert::TryFrom;
-use std::ffi::CStr;
+use std::ffi::{CStr, CString};
 
 enum TimestampType {
     None,
@@ -11,6 +11,18 @@ enum TimestampType {
     Mono,
 }
 
+/// A single decoded journal entry.
+///
+/// `fields` holds the raw `KEY -> value` pairs while `partial` flags entries
+/// carrying `CONTAINER_PARTIAL_MESSAGE=true`, i.e. a `MESSAGE` that is one
+/// fragment of a log line chunked across several records. Callers can buffer
+/// partial entries and reassemble the full line once a non-partial entry
+/// arrives.
+pub struct JournalEntry {
+    pub fields: HashMap<String, String>,
+    pub partial: bool,
+}
+
 pub struct Journal {
     // NOTE: Function invoking sd_journal in non-const context are mut.
     // This is because we are using a C FFI.
@@ -31,11 +43,30 @@ impl Drop for Journal {
 
 impl Journal {
     pub fn new() -> Journal {
+        Journal::with_flags(journal_c::SD_JOURNAL_LOCAL_ONLY)
+    }
+
+    pub fn with_flags(flags: c_int) -> Journal {
+        // https://man7.org/linux/man-pages/man3/sd_journal_open.3.html
+        let mut handle = std::ptr::null_mut() as *mut journal_c::sd_journal;
+
+        ffi_invoke_and_expect!(journal_c::sd_journal_open(&mut handle, flags));
+
+        Journal {
+            journal_handle: handle,
+            timestamp: TimestampType::Real,
+        }
+    }
+
+    pub fn with_namespace(namespace: &str, flags: c_int) -> Journal {
+        // https://man7.org/linux/man-pages/man3/sd_journal_open_namespace.3.html
         let mut handle = std::ptr::null_mut() as *mut journal_c::sd_journal;
+        let namespace = CString::new(namespace).unwrap();
 
-        ffi_invoke_and_expect!(journal_c::sd_journal_open(
+        ffi_invoke_and_expect!(journal_c::sd_journal_open_namespace(
             &mut handle,
-            journal_c::SD_JOURNAL_LOCAL_ONLY
+            namespace.as_ptr(),
+            flags
         ));
 
         Journal {
@@ -44,13 +75,48 @@ impl Journal {
         }
     }
 
-    // TODO: Make this async so that when we reach the end we wait via sd_journal_wait()
-    // https://man7.org/linux/man-pages/man3/sd_journal_wait.3.html
-    pub fn read(&mut self) -> Option<HashMap<String, String>> {
+    // For a follow-style tail that waits on new entries see read_next().
+    pub fn read(&mut self) -> Option<JournalEntry> {
         self.advance()
     }
 
-    fn advance(&mut self) -> Option<HashMap<String, String>> {
+    #[cfg(feature = "async")]
+    pub async fn read_next(&mut self) -> Option<JournalEntry> {
+        // https://man7.org/linux/man-pages/man3/sd_journal_wait.3.html
+        // Follow-style counterpart to read(): when sd_journal_next hits the end we
+        // wait on the journal's pollable fd rather than spinning.
+        use std::os::unix::io::{AsRawFd, RawFd};
+        use tokio::io::unix::AsyncFd;
+
+        // A borrowed descriptor: the fd is owned by the journal handle and must
+        // not be closed when the AsyncFd registration is dropped.
+        struct JournalFd(RawFd);
+        impl AsRawFd for JournalFd {
+            fn as_raw_fd(&self) -> RawFd {
+                self.0
+            }
+        }
+
+        loop {
+            if let Some(entry) = self.advance() {
+                return Some(entry);
+            }
+
+            // https://man7.org/linux/man-pages/man3/sd_journal_get_fd.3.html
+            // Calling get_fd is what arms the inotify-based change notification.
+            let fd = ffi_invoke_and_expect!(journal_c::sd_journal_get_fd(self.journal_handle));
+
+            let async_fd = AsyncFd::new(JournalFd(fd)).ok()?;
+            let mut guard = async_fd.readable().await.ok()?;
+            guard.clear_ready();
+
+            // https://man7.org/linux/man-pages/man3/sd_journal_process.3.html
+            // Must be called after every wakeup; returns SD_JOURNAL_NOP/APPEND/INVALIDATE.
+            ffi_invoke_and_expect!(journal_c::sd_journal_process(self.journal_handle));
+        }
+    }
+
+    fn advance(&mut self) -> Option<JournalEntry> {
         // https://www.man7.org/linux/man-pages/man3/sd_journal_next.3.html
         // According to the man pages if we have reached the end we will return 0 otherwise 1 will be returned.
         let inc = ffi_invoke_and_expect!(journal_c::sd_journal_next(self.journal_handle));
@@ -62,7 +128,101 @@ impl Journal {
         None
     }
 
-    fn get_journal_monotonic(&mut self) -> u64 {
+    pub fn add_match(&mut self, field: &str, value: &str) {
+        // https://man7.org/linux/man-pages/man3/sd_journal_add_match.3.html
+        // Same field OR's together, distinct fields AND together.
+        let data = format!("{}={}", field, value);
+
+        ffi_invoke_and_expect!(journal_c::sd_journal_add_match(
+            self.journal_handle,
+            data.as_ptr() as *const c_void,
+            data.len() as size_t
+        ));
+    }
+
+    pub fn add_disjunction(&mut self) {
+        // https://man7.org/linux/man-pages/man3/sd_journal_add_disjunction.3.html
+        ffi_invoke_and_expect!(journal_c::sd_journal_add_disjunction(self.journal_handle));
+    }
+
+    pub fn add_conjunction(&mut self) {
+        // https://man7.org/linux/man-pages/man3/sd_journal_add_conjunction.3.html
+        ffi_invoke_and_expect!(journal_c::sd_journal_add_conjunction(self.journal_handle));
+    }
+
+    pub fn flush_matches(&mut self) {
+        // https://man7.org/linux/man-pages/man3/sd_journal_flush_matches.3.html
+        unsafe {
+            journal_c::sd_journal_flush_matches(self.journal_handle);
+        }
+    }
+
+    pub fn seek_head(&mut self) {
+        // https://man7.org/linux/man-pages/man3/sd_journal_seek_head.3.html
+        ffi_invoke_and_expect!(journal_c::sd_journal_seek_head(self.journal_handle));
+    }
+
+    pub fn seek_tail(&mut self) {
+        // https://man7.org/linux/man-pages/man3/sd_journal_seek_tail.3.html
+        ffi_invoke_and_expect!(journal_c::sd_journal_seek_tail(self.journal_handle));
+    }
+
+    pub fn seek_realtime(&mut self, timestamp: NaiveDateTime) {
+        // https://man7.org/linux/man-pages/man3/sd_journal_seek_realtime_usec.3.html
+        ffi_invoke_and_expect!(journal_c::sd_journal_seek_realtime_usec(
+            self.journal_handle,
+            realtime_to_usec(timestamp)
+        ));
+    }
+
+    pub fn seek_monotonic(&mut self, boot_id: journal_c::sd_id128_t, usec: u64) {
+        // https://man7.org/linux/man-pages/man3/sd_journal_seek_monotonic_usec.3.html
+        ffi_invoke_and_expect!(journal_c::sd_journal_seek_monotonic_usec(
+            self.journal_handle,
+            boot_id,
+            usec
+        ));
+    }
+
+    pub fn get_cursor(&mut self) -> String {
+        // https://man7.org/linux/man-pages/man3/sd_journal_get_cursor.3.html
+        // The C string is heap-allocated by the library and must be freed once
+        // copied into an owned String.
+        let mut cursor_ptr = std::ptr::null_mut() as *mut libc::c_char;
+
+        ffi_invoke_and_expect!(journal_c::sd_journal_get_cursor(
+            self.journal_handle,
+            &mut cursor_ptr
+        ));
+
+        unsafe {
+            let cursor = CStr::from_ptr(cursor_ptr).to_str().unwrap().to_string();
+            libc::free(cursor_ptr as *mut c_void);
+            cursor
+        }
+    }
+
+    pub fn seek_cursor(&mut self, cursor: &str) {
+        // https://man7.org/linux/man-pages/man3/sd_journal_seek_cursor.3.html
+        let cursor = CString::new(cursor).unwrap();
+
+        ffi_invoke_and_expect!(journal_c::sd_journal_seek_cursor(
+            self.journal_handle,
+            cursor.as_ptr()
+        ));
+    }
+
+    pub fn test_cursor(&mut self, cursor: &str) -> bool {
+        // https://man7.org/linux/man-pages/man3/sd_journal_test_cursor.3.html
+        let cursor = CString::new(cursor).unwrap();
+
+        ffi_invoke_and_expect!(journal_c::sd_journal_test_cursor(
+            self.journal_handle,
+            cursor.as_ptr()
+        )) > 0
+    }
+
+    fn get_journal_monotonic(&mut self) -> (u64, journal_c::sd_id128_t) {
         // https://man7.org/linux/man-pages/man3/sd_journal_get_monotonic_usec.3.html
         let mut usec: u64 = 0;
         let mut boot_id = journal_c::sd_id128_t::new();
@@ -73,7 +233,7 @@ impl Journal {
             &mut boot_id
         ));
 
-        usec
+        (usec, boot_id)
     }
 
     fn get_journal_realtime(&mut self) -> NaiveDateTime {
@@ -85,10 +245,50 @@ impl Journal {
             &mut usec,
         ));
 
-        NaiveDateTime::from_timestamp(i64::try_from(usec).unwrap(), 0)
+        // The realtime clock is reported in microseconds since the epoch, so
+        // split it into whole seconds and the leftover nanoseconds rather than
+        // feeding the raw usec count in as seconds.
+        let secs = i64::try_from(usec / 1_000_000).unwrap();
+        let nanos = u32::try_from(usec % 1_000_000).unwrap() * 1_000;
+
+        NaiveDateTime::from_timestamp(secs, nanos)
     }
 
-    fn obtain_journal_data(&mut self) -> HashMap<String, String> {
+    pub fn get_priority(&mut self) -> Option<u8> {
+        // https://man7.org/linux/man-pages/man3/sd_journal_get_data.3.html
+        // Returns the syslog priority (0-7), or None when PRIORITY is absent.
+        let field = CString::new("PRIORITY").unwrap();
+        let mut data_ptr = std::ptr::null_mut() as *mut c_void;
+        let mut len: size_t = 0;
+
+        // sd_journal_get_data returns -ENOENT when the entry carries no PRIORITY
+        // field, which is an ordinary condition (e.g. a send() without an
+        // explicit PRIORITY=), not an FFI error — surface it as None rather than
+        // routing the negative return through the panicking macro.
+        let ret = unsafe {
+            journal_c::sd_journal_get_data(
+                self.journal_handle,
+                field.as_ptr(),
+                &mut data_ptr,
+                &mut len,
+            )
+        };
+
+        if ret < 0 {
+            return None;
+        }
+
+        let data = unsafe {
+            let c_str: &CStr = CStr::from_ptr(data_ptr as *const _);
+            c_str.to_str().unwrap()
+        };
+
+        data.find('=')
+            .and_then(|idx| data[idx + 1..].parse::<u8>().ok())
+            .filter(|priority| *priority <= 7)
+    }
+
+    fn obtain_journal_data(&mut self) -> JournalEntry {
         let mut data_ptr = std::ptr::null_mut() as *mut c_void;
         let mut len: size_t = 0;
 
@@ -127,12 +327,17 @@ impl Journal {
             }
         }
 
-        self.obtain_journal_timestamp(&journal_entries);
+        self.obtain_journal_timestamp(&mut journal_entries);
 
-        journal_entries
+        let partial = is_partial_message(&journal_entries);
+
+        JournalEntry {
+            fields: journal_entries,
+            partial,
+        }
     }
 
-    fn obtain_journal_timestamp(&mut self, mut journal_entries: &HashMap<String, String>) {
+    fn obtain_journal_timestamp(&mut self, journal_entries: &mut HashMap<String, String>) {
         let mut ts_opt: Option<String> = None;
         let mut key = "";
 
@@ -141,13 +346,19 @@ impl Journal {
                 key = journal_c::JOURNAL_REALTIME_TIMESTAMP_KEY;
 
                 let ts = self.get_journal_realtime();
-                // format
+                ts_opt = Some(ts.to_string());
             }
             TimestampType::Mono => {
                 key = journal_c::JOURNAL_MONOTOMIC_TIMESTAMP_KEY;
 
-                let ts = self.get_journal_monotonic();
-                // format
+                let (usec, boot_id) = self.get_journal_monotonic();
+                let boot_id: String = boot_id
+                    .bytes
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect();
+
+                ts_opt = Some(format!("{};{}", usec, boot_id));
             }
             _ => {}
         }
@@ -158,8 +369,99 @@ impl Journal {
     }
 }
 
+// Convert a realtime timestamp to the unsigned usec clock, clamping pre-epoch
+// values to 0 rather than overflowing/panicking.
+fn realtime_to_usec(timestamp: NaiveDateTime) -> u64 {
+    let secs = timestamp.timestamp();
+    if secs < 0 {
+        0
+    } else {
+        secs as u64 * 1_000_000 + u64::from(timestamp.timestamp_subsec_micros())
+    }
+}
+
+/// Detect whether an entry's `MESSAGE` is a continuation fragment, signalled by
+/// `CONTAINER_PARTIAL_MESSAGE=true`.
+fn is_partial_message(fields: &HashMap<String, String>) -> bool {
+    fields
+        .get("CONTAINER_PARTIAL_MESSAGE")
+        .map_or(false, |val| val == "true")
+}
+
+// Wrap each KEY=value field in an iovec pointing at its bytes. sendv takes the
+// length explicitly, so no trailing NUL is required.
+fn build_iovecs(fields: &[&str]) -> Vec<iovec> {
+    fields
+        .iter()
+        .map(|field| iovec {
+            iov_base: field.as_ptr() as *mut c_void,
+            iov_len: field.len() as size_t,
+        })
+        .collect()
+}
+
+/// Submit a structured entry built from `KEY=value` fields.
+pub fn send(fields: &[&str]) {
+    // https://man7.org/linux/man-pages/man3/sd_journal_sendv.3.html
+    let iov = build_iovecs(fields);
+
+    ffi_invoke_and_expect!(journal_c::sd_journal_sendv(
+        iov.as_ptr(),
+        iov.len() as c_int
+    ));
+}
+
+/// Submit a single `msg` at the given syslog `priority`.
+pub fn print(priority: u32, msg: &str) {
+    send(&[
+        &format!("PRIORITY={}", priority),
+        &format!("MESSAGE={}", msg),
+    ]);
+}
+
 #[test]
 fn test_journal_new() {
     // Test should simply not panic
     let _j: Journal = Journal::new();
 }
+
+#[test]
+fn test_is_partial_message() {
+    let mut fields: HashMap<String, String> = HashMap::new();
+    assert!(!is_partial_message(&fields));
+
+    fields.insert("CONTAINER_PARTIAL_MESSAGE".to_string(), "false".to_string());
+    assert!(!is_partial_message(&fields));
+
+    fields.insert("CONTAINER_PARTIAL_MESSAGE".to_string(), "true".to_string());
+    assert!(is_partial_message(&fields));
+}
+
+#[test]
+fn test_build_iovecs() {
+    let fields = ["PRIORITY=6", "MESSAGE=msg"];
+    let iov = build_iovecs(&fields);
+
+    assert_eq!(iov.len(), 2);
+
+    let decoded: Vec<&str> = iov
+        .iter()
+        .map(|v| unsafe {
+            let bytes = std::slice::from_raw_parts(v.iov_base as *const u8, v.iov_len);
+            std::str::from_utf8(bytes).unwrap()
+        })
+        .collect();
+
+    assert_eq!(decoded, vec!["PRIORITY=6", "MESSAGE=msg"]);
+}
+
+#[test]
+fn test_realtime_to_usec() {
+    // A post-epoch timestamp converts with its sub-second micros preserved.
+    let ts = NaiveDateTime::from_timestamp(1, 500_000_000);
+    assert_eq!(realtime_to_usec(ts), 1_000_000 + 500_000);
+
+    // Pre-epoch timestamps clamp to the start of the journal instead of panicking.
+    let pre_epoch = NaiveDateTime::from_timestamp(-1, 0);
+    assert_eq!(realtime_to_usec(pre_epoch), 0);
+}
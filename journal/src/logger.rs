@@ -0,0 +1,71 @@
+use crate::journal;
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+/// A [`log`] backend that routes records straight into the journal.
+///
+/// Each record is emitted through the `sd_journal_sendv` write path with the
+/// structured metadata journald expects (`PRIORITY`, `MESSAGE`) alongside the
+/// record's `CODE_FILE`, `CODE_LINE` and `TARGET`.
+pub struct JournaldLogger;
+
+/// Map a [`log::Level`] onto the corresponding syslog priority.
+///
+/// Rust's `log` levels are coarser than syslog's, so `Debug` and `Trace` both
+/// collapse onto `LOG_DEBUG` (7).
+fn level_to_priority(level: Level) -> u32 {
+    match level {
+        Level::Error => 3,
+        Level::Warn => 4,
+        Level::Info => 6,
+        Level::Debug => 7,
+        Level::Trace => 7,
+    }
+}
+
+impl Log for JournaldLogger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let mut fields = vec![
+            format!("PRIORITY={}", level_to_priority(record.level())),
+            format!("MESSAGE={}", record.args()),
+            format!("TARGET={}", record.target()),
+        ];
+
+        if let Some(file) = record.file() {
+            fields.push(format!("CODE_FILE={}", file));
+        }
+
+        if let Some(line) = record.line() {
+            fields.push(format!("CODE_LINE={}", line));
+        }
+
+        let refs: Vec<&str> = fields.iter().map(String::as_str).collect();
+        journal::send(&refs);
+    }
+
+    fn flush(&self) {}
+}
+
+/// Register the [`JournaldLogger`] as the global `log` sink.
+///
+/// https://docs.rs/log/latest/log/fn.set_boxed_logger.html
+pub fn init() -> Result<(), SetLoggerError> {
+    log::set_boxed_logger(Box::new(JournaldLogger))
+        .map(|()| log::set_max_level(LevelFilter::Trace))
+}
+
+#[test]
+fn test_level_to_priority() {
+    assert_eq!(level_to_priority(Level::Error), 3);
+    assert_eq!(level_to_priority(Level::Warn), 4);
+    assert_eq!(level_to_priority(Level::Info), 6);
+    assert_eq!(level_to_priority(Level::Debug), 7);
+    assert_eq!(level_to_priority(Level::Trace), 7);
+}